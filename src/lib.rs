@@ -1,30 +1,283 @@
-use std::sync::{mpsc, Arc, Mutex};
+use std::collections::VecDeque;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
 use std::thread;
+use std::time::{Duration, Instant};
+
+mod request;
+mod response;
+mod router;
+
+pub use request::{Method, Request, RequestError};
+pub use response::Response;
+pub use router::Router;
+
+/// A worker that panics this many times within `PANIC_WINDOW` has its thread
+/// retired and replaced by a fresh thread under the same id with a reset
+/// panic count. The slot is never left without a thread, so jobs that keep
+/// panicking keep causing replacements.
+const MAX_PANICS_PER_WINDOW: usize = 3;
+const PANIC_WINDOW: Duration = Duration::from_secs(10);
 
 /// The errors that can be returned by the `ThreadPool`.
+#[derive(Debug)]
 pub enum PoolError {
     CreationError(&'static str),
+    /// Returned by `execute`/`try_execute` once the pool has started shutting down.
+    ShuttingDown,
+    /// Returned by `try_execute` when the queue is at capacity.
+    QueueFull,
 }
 
 type Job = Box<dyn FnOnce() + Send + 'static>;
 
+/// A cloneable handle used to observe and trigger shutdown of a `ThreadPool`
+/// from outside of it, e.g. from a signal handler.
+///
+/// Flipping the handle does not itself join the pool's worker threads; it
+/// only flags the pool as shutting down so that `execute` starts rejecting
+/// new jobs and callers (such as `main`'s accept loop) can notice and stop
+/// feeding it work. Call `ThreadPool::shutdown` to actually drain the queue
+/// and join the workers.
+#[derive(Clone)]
+pub struct ShutdownHandle {
+    queue: Arc<JobQueue>,
+}
+
+impl ShutdownHandle {
+    /// Returns `true` once shutdown has been triggered.
+    pub fn is_shutdown(&self) -> bool {
+        self.queue.is_shutdown.load(Ordering::SeqCst)
+    }
+
+    /// Flags the pool as shutting down.
+    pub fn trigger(&self) {
+        self.queue.shutdown();
+    }
+}
+
+/// A cloneable handle used to read a `ThreadPool`'s live metrics from
+/// outside of it, e.g. from a `/metrics` route handler.
+#[derive(Clone)]
+pub struct MetricsHandle {
+    queue: Arc<JobQueue>,
+}
+
+impl MetricsHandle {
+    /// Returns a snapshot of the pool's current metrics.
+    pub fn stats(&self) -> PoolStats {
+        self.queue.stats()
+    }
+}
+
+/// A point-in-time snapshot of a `ThreadPool`'s job metrics.
+#[derive(Debug, Clone, Copy)]
+pub struct PoolStats {
+    pub jobs_submitted: usize,
+    pub jobs_completed: usize,
+    pub jobs_executing: usize,
+    pub queue_depth: usize,
+}
+
+impl fmt::Display for PoolStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "jobs_submitted {}", self.jobs_submitted)?;
+        writeln!(f, "jobs_completed {}", self.jobs_completed)?;
+        writeln!(f, "jobs_executing {}", self.jobs_executing)?;
+        writeln!(f, "queue_depth {}", self.queue_depth)
+    }
+}
+
+/// A bounded job queue shared between the pool and its workers.
+///
+/// Mirrors the classic condvar-based bounded queue: `not_empty` wakes workers
+/// parked waiting for a job, `not_full` wakes producers parked waiting for
+/// room. Both condvars are paired with the same `jobs` mutex, so a waiter
+/// never misses a wakeup between checking the predicate and parking.
+struct JobQueue {
+    jobs: Mutex<VecDeque<Job>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    capacity: usize,
+    is_shutdown: AtomicBool,
+    jobs_submitted: AtomicUsize,
+    jobs_completed: AtomicUsize,
+    jobs_executing: AtomicUsize,
+    queue_depth: AtomicUsize,
+}
+
+impl JobQueue {
+    fn new(capacity: usize) -> Self {
+        Self {
+            jobs: Mutex::new(VecDeque::with_capacity(capacity)),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            capacity,
+            is_shutdown: AtomicBool::new(false),
+            jobs_submitted: AtomicUsize::new(0),
+            jobs_completed: AtomicUsize::new(0),
+            jobs_executing: AtomicUsize::new(0),
+            queue_depth: AtomicUsize::new(0),
+        }
+    }
+
+    /// Pushes `job` to the back of the queue, blocking until there is room
+    /// or the queue is shut down.
+    fn push(&self, job: Job) -> Result<(), PoolError> {
+        let mut jobs = self.jobs.lock().unwrap();
+
+        loop {
+            if self.is_shutdown.load(Ordering::SeqCst) {
+                return Err(PoolError::ShuttingDown);
+            }
+
+            if jobs.len() < self.capacity {
+                break;
+            }
+
+            jobs = self.not_full.wait(jobs).unwrap();
+        }
+
+        jobs.push_back(job);
+        drop(jobs);
+        self.jobs_submitted.fetch_add(1, Ordering::SeqCst);
+        self.queue_depth.fetch_add(1, Ordering::SeqCst);
+        self.not_empty.notify_one();
+
+        Ok(())
+    }
+
+    /// Pushes `job` without blocking, failing if the queue is at capacity or
+    /// shut down.
+    fn try_push(&self, job: Job) -> Result<(), PoolError> {
+        let mut jobs = self.jobs.lock().unwrap();
+
+        if self.is_shutdown.load(Ordering::SeqCst) {
+            return Err(PoolError::ShuttingDown);
+        }
+
+        if jobs.len() >= self.capacity {
+            return Err(PoolError::QueueFull);
+        }
+
+        jobs.push_back(job);
+        drop(jobs);
+        self.jobs_submitted.fetch_add(1, Ordering::SeqCst);
+        self.queue_depth.fetch_add(1, Ordering::SeqCst);
+        self.not_empty.notify_one();
+
+        Ok(())
+    }
+
+    /// Pops the next job, blocking while the queue is empty. Returns `None`
+    /// once the queue has been shut down and fully drained.
+    fn pop(&self) -> Option<Job> {
+        let mut jobs = self.jobs.lock().unwrap();
+
+        loop {
+            if let Some(job) = jobs.pop_front() {
+                drop(jobs);
+                self.queue_depth.fetch_sub(1, Ordering::SeqCst);
+                self.not_full.notify_one();
+                return Some(job);
+            }
+
+            if self.is_shutdown.load(Ordering::SeqCst) {
+                return None;
+            }
+
+            jobs = self.not_empty.wait(jobs).unwrap();
+        }
+    }
+
+    fn shutdown(&self) {
+        let jobs = self.jobs.lock().unwrap();
+        self.is_shutdown.store(true, Ordering::SeqCst);
+        drop(jobs);
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+    }
+
+    /// Marks a job as having started executing on a worker.
+    fn mark_executing(&self) {
+        self.jobs_executing.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Marks the currently executing job as finished (whether it completed
+    /// normally or panicked).
+    fn mark_completed(&self) {
+        self.jobs_executing.fetch_sub(1, Ordering::SeqCst);
+        self.jobs_completed.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn stats(&self) -> PoolStats {
+        PoolStats {
+            jobs_submitted: self.jobs_submitted.load(Ordering::SeqCst),
+            jobs_completed: self.jobs_completed.load(Ordering::SeqCst),
+            jobs_executing: self.jobs_executing.load(Ordering::SeqCst),
+            queue_depth: self.queue_depth.load(Ordering::SeqCst),
+        }
+    }
+}
+
+/// Counts panics observed by a single worker within a fixed window that
+/// restarts once `PANIC_WINDOW` has elapsed, so transient panics don't
+/// accumulate forever and trip the retirement limit.
+struct PanicTracker {
+    count: AtomicUsize,
+    window_start: Mutex<Instant>,
+}
+
+impl PanicTracker {
+    fn new() -> Self {
+        Self {
+            count: AtomicUsize::new(0),
+            window_start: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Records a panic and returns how many panics have been observed
+    /// within the current window, including this one.
+    fn record_panic(&self) -> usize {
+        let mut window_start = self.window_start.lock().unwrap();
+
+        if window_start.elapsed() > PANIC_WINDOW {
+            *window_start = Instant::now();
+            self.count.store(0, Ordering::SeqCst);
+        }
+
+        self.count.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    fn count(&self) -> usize {
+        if self.window_start.lock().unwrap().elapsed() > PANIC_WINDOW {
+            return 0;
+        }
+
+        self.count.load(Ordering::SeqCst)
+    }
+}
+
 /// The thread pool.
-/// 
+///
 /// # Description
-/// 
+///
 /// Allows you to execute tasks concurrently by maintaining a pool of threads
 /// and executing passed tasks.
-/// 
-/// To create an instance of the `ThreadPool` you can use either the `new` or the `build` function. 
-/// The 'execute' method takes a task to execute and sends it to the sending-halt of a channel,
-/// then an arbitrary thread receives from the receiving-halt of the channel the task and executes it.
+///
+/// To create an instance of the `ThreadPool` you can use either the `new` or the `build` function.
+/// The `execute` method pushes a task onto a bounded, shared job queue; an
+/// arbitrary worker thread pops it off the queue and runs it.
 pub struct ThreadPool {
-    workers: Vec<Worker>,
-    sender: Option<mpsc::Sender<Job>>,
+    workers: Vec<Arc<Worker>>,
+    queue: Arc<JobQueue>,
 }
 
 impl ThreadPool {
-    /// Constructs an instance of the `ThreadPool`.
+    /// Constructs an instance of the `ThreadPool` with a queue capacity of
+    /// `size * 4`.
     ///
     /// The size is number of threads in the pool.
     ///
@@ -34,7 +287,20 @@ impl ThreadPool {
     pub fn new(size: usize) -> Self {
         assert!(size > 0);
 
-        Self::init(size)
+        Self::init(size, size * 4)
+    }
+
+    /// Constructs an instance of the `ThreadPool` with an explicit queue
+    /// `capacity`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` or `capacity` is zero.
+    pub fn with_capacity(size: usize, capacity: usize) -> Self {
+        assert!(size > 0);
+        assert!(capacity > 0);
+
+        Self::init(size, capacity)
     }
 
     /// Builds an instance of the `ThreadPool`.
@@ -42,78 +308,189 @@ impl ThreadPool {
     /// The auxuillary function for creating a thread pool.
     ///
     /// The size is number of threads in the pool.
-    /// 
+    ///
     /// Returns `Err` if occured an error, otherwise returns `Ok`.
     pub fn build(size: usize) -> Result<Self, PoolError> {
         if size == 0 {
             return Err(PoolError::CreationError("Number of threads equals zero"));
         }
 
-        Ok(Self::init(size))
+        Ok(Self::init(size, size * 4))
     }
 
-    fn init(size: usize) -> Self {
+    fn init(size: usize, capacity: usize) -> Self {
         let mut workers: Vec<_> = Vec::with_capacity(size);
 
-        let (sender, receiver) = mpsc::channel();
-        let shared_receiver = Arc::new(Mutex::new(receiver));
+        let queue = Arc::new(JobQueue::new(capacity));
 
         for i in 0..size {
-            workers.push(Worker::new(i, Arc::clone(&shared_receiver)));
+            workers.push(Worker::spawn(i, Arc::clone(&queue)));
         }
 
-        Self {
-            workers,
-            sender: Some(sender),
+        Self { workers, queue }
+    }
+
+    /// Returns a cloneable `ShutdownHandle` that can be used to trigger
+    /// and observe shutdown of this pool from outside of it.
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        ShutdownHandle {
+            queue: Arc::clone(&self.queue),
         }
     }
 
-    pub fn execute<F>(&mut self, f: F)
+    /// Submits `f` to be run on one of the pool's worker threads.
+    ///
+    /// Blocks until there is room in the queue. Returns
+    /// `Err(PoolError::ShuttingDown)` instead of queuing `f` once `shutdown`
+    /// has been called or a `ShutdownHandle` has been triggered.
+    pub fn execute<F>(&mut self, f: F) -> Result<(), PoolError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.queue.push(Box::new(f))
+    }
+
+    /// Submits `f` without blocking.
+    ///
+    /// Returns `Err(PoolError::QueueFull)` if the queue is at capacity, or
+    /// `Err(PoolError::ShuttingDown)` if the pool has started shutting down.
+    pub fn try_execute<F>(&mut self, f: F) -> Result<(), PoolError>
     where
         F: FnOnce() + Send + 'static,
     {
-        self.sender.as_mut().unwrap().send(Box::new(f)).unwrap();
+        self.queue.try_push(Box::new(f))
+    }
+
+    /// Returns a cloneable `MetricsHandle` that can be used to read this
+    /// pool's live job metrics from outside of it.
+    pub fn metrics_handle(&self) -> MetricsHandle {
+        MetricsHandle {
+            queue: Arc::clone(&self.queue),
+        }
+    }
+
+    /// Returns a snapshot of the pool's current job metrics.
+    pub fn stats(&self) -> PoolStats {
+        self.queue.stats()
+    }
+
+    /// Returns how many times the worker with the given `id` has panicked
+    /// within the current panic window, or `None` if no worker has that id.
+    ///
+    /// If that worker has since been retired and replaced, this reflects
+    /// the replacement's (reset) count, not the retired worker's.
+    pub fn worker_panic_count(&self, id: usize) -> Option<usize> {
+        self.workers
+            .iter()
+            .find(|worker| worker.id == id)
+            .map(|worker| worker.panic_tracker.lock().unwrap().count())
+    }
+
+    /// Stops the pool from accepting new jobs, waits for already-queued jobs
+    /// to finish, and joins all worker threads before returning.
+    ///
+    /// After this returns, every worker has exited cleanly.
+    pub fn shutdown(self) {
+        self.queue.shutdown();
+
+        self.join_workers();
+    }
+
+    /// Joins every worker thread that hasn't already been joined, logging
+    /// only the workers that actually had a thread left to join.
+    ///
+    /// A retired worker may have already spawned its own replacement by the
+    /// time this runs, in which case `worker.thread` holds the replacement's
+    /// handle rather than the retired thread's — either way, whatever is
+    /// currently there gets joined.
+    fn join_workers(&self) {
+        for worker in &self.workers {
+            // Take the handle in its own statement so the lock is released
+            // before joining: a retiring thread locks `worker.thread` to
+            // store its replacement, which is then picked up on the next turn.
+            loop {
+                let thread = worker.thread.lock().unwrap().take();
+                let Some(thread) = thread else { break };
+                println!("Shutting down worker {}", worker.id);
+                thread.join().unwrap();
+            }
+        }
     }
 }
 
+/// A pool slot identified by `id`. `thread` and `panic_tracker` are behind
+/// `Mutex`es because a worker that retires replaces both in place: the pool
+/// keeps the same `Worker` (and the same id), just running on a fresh
+/// thread with a fresh panic count, so pool capacity never shrinks.
 struct Worker {
-    thread: Option<thread::JoinHandle<()>>,
     id: usize,
+    thread: Mutex<Option<thread::JoinHandle<()>>>,
+    panic_tracker: Mutex<Arc<PanicTracker>>,
 }
 
 impl Worker {
-    fn new(id: usize, receiver: Arc<Mutex<mpsc::Receiver<Job>>>) -> Self {
-        let thread = thread::spawn(move || loop {
-            let job = receiver.lock().unwrap().recv();
+    /// Spawns a worker with the given `id` serving jobs from `queue`.
+    fn spawn(id: usize, queue: Arc<JobQueue>) -> Arc<Self> {
+        let worker = Arc::new(Self {
+            id,
+            thread: Mutex::new(None),
+            panic_tracker: Mutex::new(Arc::new(PanicTracker::new())),
+        });
+
+        Self::spawn_thread(&worker, queue);
 
-            match job {
-                Ok(job) => {
+        worker
+    }
+
+    /// Runs the worker's job loop on a fresh thread, storing its handle on
+    /// `worker`. Called once from `spawn` and again each time a generation
+    /// retires to hand the id off to its replacement.
+    fn spawn_thread(worker: &Arc<Worker>, queue: Arc<JobQueue>) {
+        let id = worker.id;
+        let panic_tracker = Arc::clone(&worker.panic_tracker.lock().unwrap());
+        let worker_for_thread = Arc::clone(worker);
+
+        let thread = thread::spawn(move || loop {
+            match queue.pop() {
+                Some(job) => {
                     println!("Worker {id} got a job; executing.");
 
-                    job();
+                    queue.mark_executing();
+                    let result = panic::catch_unwind(AssertUnwindSafe(job));
+                    queue.mark_completed();
+
+                    if result.is_err() {
+                        let panics = panic_tracker.record_panic();
+                        eprintln!(
+                            "Worker {id} panicked while running a job ({panics} panic(s) in the last {PANIC_WINDOW:?})."
+                        );
+
+                        if panics >= MAX_PANICS_PER_WINDOW {
+                            eprintln!(
+                                "Worker {id} panicked {MAX_PANICS_PER_WINDOW} times in {PANIC_WINDOW:?}; retiring it and spawning its replacement."
+                            );
+                            *worker_for_thread.panic_tracker.lock().unwrap() = Arc::new(PanicTracker::new());
+                            Self::spawn_thread(&worker_for_thread, Arc::clone(&queue));
+                            break;
+                        }
+                    }
                 }
-                Err(_) => {
+                None => {
                     println!("Worker {id} is disconnected. shutting down.");
                     break;
                 }
-            }           
+            }
         });
 
-        Self { thread: Some(thread), id }
+        *worker.thread.lock().unwrap() = Some(thread);
     }
 }
 
 impl Drop for ThreadPool {
     fn drop(&mut self) {
-        drop(self.sender.take());
-
-        for worker in &mut self.workers {
-            println!("Shutting down worker {}", worker.id);
+        self.queue.shutdown();
 
-            if let Some(thread) = worker.thread.take() {
-                thread.join().unwrap();
-            }
-        }
+        self.join_workers();
     }
 }
 
@@ -131,7 +508,7 @@ mod tests {
         fn wrong_size_new_function() {
             ThreadPool::new(0);
         }
-    
+
         #[test]
         fn wrong_size_build_function() {
             assert!(matches!(ThreadPool::build(0), Err(PoolError::CreationError("Number of threads equals zero"))));
@@ -139,11 +516,10 @@ mod tests {
 
         #[test]
         fn init() {
-            let thread_pool = ThreadPool::init(3);
+            let thread_pool = ThreadPool::init(3, 12);
 
             assert_eq!(thread_pool.workers.capacity(), 3);
-            
-            assert!(matches!(thread_pool.sender, Some(_)));
+            assert_eq!(thread_pool.queue.capacity, 12);
         }
 
         #[test]
@@ -156,26 +532,136 @@ mod tests {
             thread_pool.execute(move || {
                 let mut r = check_clone.lock().unwrap();
                 *r = true;
-            });
+            }).unwrap();
 
             thread::sleep(Duration::from_secs(1));
 
-            assert_eq!(*check.lock().unwrap(), true);
+            assert!(*check.lock().unwrap());
+        }
+
+        #[test]
+        fn try_execute_rejects_when_queue_is_full() {
+            let mut thread_pool = ThreadPool::with_capacity(1, 1);
+
+            thread_pool.try_execute(|| thread::sleep(Duration::from_millis(500))).unwrap();
+            thread::sleep(Duration::from_millis(50));
+            thread_pool.try_execute(|| {}).unwrap();
+
+            assert!(matches!(thread_pool.try_execute(|| {}), Err(PoolError::QueueFull)));
+        }
+
+        #[test]
+        fn execute_after_shutdown_is_rejected() {
+            let mut thread_pool = ThreadPool::new(1);
+            let handle = thread_pool.shutdown_handle();
+
+            handle.trigger();
+
+            assert!(matches!(thread_pool.execute(|| {}), Err(PoolError::ShuttingDown)));
+        }
+
+        #[test]
+        fn panicking_job_does_not_kill_the_worker() {
+            let mut thread_pool = ThreadPool::new(1);
+
+            thread_pool.execute(|| panic!("boom")).unwrap();
+            thread::sleep(Duration::from_millis(200));
+
+            assert_eq!(thread_pool.worker_panic_count(0), Some(1));
+
+            let check = Arc::new(Mutex::new(false));
+            let check_clone = Arc::clone(&check);
+
+            thread_pool.execute(move || {
+                *check_clone.lock().unwrap() = true;
+            }).unwrap();
+            thread::sleep(Duration::from_millis(200));
+
+            assert!(*check.lock().unwrap());
+        }
+
+        #[test]
+        fn retired_worker_is_replaced_and_keeps_serving_jobs() {
+            let mut thread_pool = ThreadPool::new(1);
+
+            for _ in 0..MAX_PANICS_PER_WINDOW {
+                thread_pool.execute(|| panic!("boom")).unwrap();
+                thread::sleep(Duration::from_millis(100));
+            }
+
+            // The worker that just retired has already spawned its
+            // replacement under the same id, with a freshly reset panic
+            // count, so pool capacity is preserved and a job submitted
+            // afterwards still gets run.
+            let check = Arc::new(Mutex::new(false));
+            let check_clone = Arc::clone(&check);
+
+            thread_pool.execute(move || {
+                *check_clone.lock().unwrap() = true;
+            }).unwrap();
+            thread::sleep(Duration::from_millis(100));
+
+            assert!(*check.lock().unwrap());
+            assert_eq!(thread_pool.worker_panic_count(0), Some(0));
+        }
+
+        #[test]
+        fn stats_reflect_submitted_and_completed_jobs() {
+            let mut thread_pool = ThreadPool::new(1);
+
+            thread_pool.execute(|| {}).unwrap();
+            thread::sleep(Duration::from_millis(200));
+
+            let stats = thread_pool.stats();
+
+            assert_eq!(stats.jobs_submitted, 1);
+            assert_eq!(stats.jobs_completed, 1);
+            assert_eq!(stats.jobs_executing, 0);
+            assert_eq!(stats.queue_depth, 0);
+        }
+
+        #[test]
+        fn stats_reflect_jobs_in_flight() {
+            let mut thread_pool = ThreadPool::with_capacity(1, 1);
+
+            thread_pool.try_execute(|| thread::sleep(Duration::from_millis(500))).unwrap();
+            thread::sleep(Duration::from_millis(50));
+
+            assert_eq!(thread_pool.stats().jobs_executing, 1);
+
+            thread_pool.try_execute(|| {}).unwrap();
+
+            assert_eq!(thread_pool.stats().queue_depth, 1);
+        }
+
+        #[test]
+        fn shutdown_joins_workers_and_drains_queue() {
+            let mut thread_pool = ThreadPool::new(2);
+
+            let check = Arc::new(Mutex::new(0));
+            let check_clone = Arc::clone(&check);
+
+            thread_pool.execute(move || {
+                *check_clone.lock().unwrap() += 1;
+            }).unwrap();
+
+            thread_pool.shutdown();
+
+            assert_eq!(*check.lock().unwrap(), 1);
         }
     }
-    
+
     mod worker {
         use super::*;
 
         #[test]
-        fn new() {
-            let (_, receiver) = mpsc::channel();
-            let receiver = Arc::new(Mutex::new(receiver)); 
+        fn spawn() {
+            let queue = Arc::new(JobQueue::new(4));
+
+            let worker = Worker::spawn(10, queue);
 
-            let worker = Worker::new(10, receiver);
-            
             assert_eq!(worker.id, 10);
-            assert!(matches!(worker.thread, Some(_)));
+            assert!(worker.thread.lock().unwrap().is_some());
         }
     }
 }
@@ -1,9 +1,27 @@
 use std::{
     env,
-    io::{BufRead, BufReader, Write},
-    net, time::Duration,
+    io::ErrorKind,
+    net, sync::Arc,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::Duration,
 };
-use multithreaded_web_server::ThreadPool;
+use multithreaded_web_server::{Method, Response, Router, ThreadPool};
+
+/// Set by `handle_sigint` and polled from the accept loop; there's no std
+/// API for installing a signal handler, so this talks to the platform's C
+/// `signal` directly instead of pulling in a crate for one `extern` call.
+static SIGINT_RECEIVED: AtomicBool = AtomicBool::new(false);
+
+const SIGINT: i32 = 2;
+
+extern "C" {
+    fn signal(signum: i32, handler: usize) -> usize;
+}
+
+extern "C" fn handle_sigint(_signum: i32) {
+    SIGINT_RECEIVED.store(true, Ordering::SeqCst);
+}
 
 fn main() {
     let mut args = env::args();
@@ -16,6 +34,7 @@ fn main() {
         eprintln!("Error: {err}");
         std::process::exit(1);
     });
+    server.set_nonblocking(true).unwrap();
 
     let threads_num = match args.next() {
         Some(v) => match v.parse::<usize>() {
@@ -26,31 +45,64 @@ fn main() {
             }
         }
         None => std::thread::available_parallelism().unwrap().into()
-    };  
+    };
 
     let mut pool = ThreadPool::new(threads_num);
+    let metrics_handle = pool.metrics_handle();
 
-    for stream in server.incoming() {
-        let stream = stream.unwrap();
-        pool.execute(|| handle_connection(stream));
+    let router = Arc::new(
+        Router::new()
+            .route(Method::Get, "/", |_req| Response::ok(read_page("hello.html")))
+            .route(Method::Get, "/sleep", |_req| {
+                thread::sleep(Duration::from_secs(5));
+                Response::ok(read_page("hello.html"))
+            })
+            .route(Method::Get, "/metrics", move |_req| {
+                Response::ok(metrics_handle.stats().to_string())
+            })
+            .not_found(|_req| Response::not_found(read_page("404.html"))),
+    );
+
+    let shutdown_handle = pool.shutdown_handle();
+
+    // SAFETY: `handle_sigint` only stores to a `'static` atomic, which is
+    // async-signal-safe, and `signal`'s C signature matches the one
+    // declared above.
+    unsafe {
+        signal(SIGINT, handle_sigint as *const () as usize);
     }
-}
 
-fn handle_connection(mut socket: net::TcpStream) {
-    let buf_reader = BufReader::new(&mut socket);
-    let status_line_request = buf_reader.lines().next().unwrap().unwrap();
-    
-    let (status_line, content) = match &status_line_request[..] {
-        "GET / HTTP/1.1" => ("HTTP/1.1 200 OK", "hello.html"),
-        "GET /sleep HTTP/1.1" => {
-            std::thread::sleep(Duration::from_secs(5));
-            ("HTTP/1.1 200 OK", "hello.html")
+    for stream in server.incoming() {
+        if SIGINT_RECEIVED.load(Ordering::SeqCst) {
+            println!("Shutting down.");
+            shutdown_handle.trigger();
+        }
+
+        if shutdown_handle.is_shutdown() {
+            break;
         }
-        _ => ("HTTP/1.1 404 Not Found", "404.html")
-    };
 
-    let content = std::fs::read_to_string(content).unwrap();
-    let content_length = content.len();
-    let response = std::format!("{status_line}\r\nContent-Length: {content_length}\r\n\r\n{content}").to_string();
-    socket.write_all(response.as_bytes()).unwrap();
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) if err.kind() == ErrorKind::WouldBlock => {
+                thread::sleep(Duration::from_millis(100));
+                continue;
+            }
+            Err(err) => {
+                eprintln!("Error: {err}");
+                continue;
+            }
+        };
+
+        let router = Arc::clone(&router);
+        if pool.execute(move || router.dispatch(stream)).is_err() {
+            eprintln!("Dropped connection: pool is shutting down");
+        }
+    }
+
+    pool.shutdown();
+}
+
+fn read_page(path: &str) -> String {
+    std::fs::read_to_string(path).unwrap()
 }
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
+
+/// The HTTP methods this server understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Delete,
+    Head,
+    Options,
+    Patch,
+}
+
+impl Method {
+    fn parse(method: &str) -> Option<Self> {
+        match method {
+            "GET" => Some(Method::Get),
+            "POST" => Some(Method::Post),
+            "PUT" => Some(Method::Put),
+            "DELETE" => Some(Method::Delete),
+            "HEAD" => Some(Method::Head),
+            "OPTIONS" => Some(Method::Options),
+            "PATCH" => Some(Method::Patch),
+            _ => None,
+        }
+    }
+}
+
+/// The errors that can occur while parsing a `Request` off the wire.
+#[derive(Debug)]
+pub enum RequestError {
+    Io(std::io::Error),
+    MalformedRequestLine(String),
+    UnknownMethod(String),
+}
+
+impl From<std::io::Error> for RequestError {
+    fn from(err: std::io::Error) -> Self {
+        RequestError::Io(err)
+    }
+}
+
+/// A parsed HTTP request line and header block.
+///
+/// The request body, if any, is not read or stored.
+pub struct Request {
+    pub method: Method,
+    pub path: String,
+    pub version: String,
+    pub headers: HashMap<String, String>,
+}
+
+impl Request {
+    /// Reads a request line and header block from `reader` and parses them
+    /// into a `Request`.
+    pub fn parse<R: Read>(reader: &mut BufReader<R>) -> Result<Self, RequestError> {
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        let request_line = request_line.trim_end();
+
+        let mut parts = request_line.split_whitespace();
+
+        let method = parts
+            .next()
+            .ok_or_else(|| RequestError::MalformedRequestLine(request_line.to_string()))?;
+        let path = parts
+            .next()
+            .ok_or_else(|| RequestError::MalformedRequestLine(request_line.to_string()))?;
+        let version = parts
+            .next()
+            .ok_or_else(|| RequestError::MalformedRequestLine(request_line.to_string()))?;
+
+        let method = Method::parse(method).ok_or_else(|| RequestError::UnknownMethod(method.to_string()))?;
+
+        let mut headers = HashMap::new();
+
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line)?;
+            let line = line.trim_end();
+
+            if line.is_empty() {
+                break;
+            }
+
+            if let Some((name, value)) = line.split_once(':') {
+                headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+            }
+        }
+
+        Ok(Self {
+            method,
+            path: path.to_string(),
+            version: version.to_string(),
+            headers,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_request_line_and_headers() {
+        let raw = "GET /sleep HTTP/1.1\r\nHost: localhost\r\nX-Test: yes\r\n\r\n";
+        let mut reader = BufReader::new(raw.as_bytes());
+
+        let request = Request::parse(&mut reader).unwrap();
+
+        assert!(matches!(request.method, Method::Get));
+        assert_eq!(request.path, "/sleep");
+        assert_eq!(request.version, "HTTP/1.1");
+        assert_eq!(request.headers.get("host"), Some(&"localhost".to_string()));
+        assert_eq!(request.headers.get("x-test"), Some(&"yes".to_string()));
+    }
+
+    #[test]
+    fn rejects_unknown_method() {
+        let raw = "FETCH / HTTP/1.1\r\n\r\n";
+        let mut reader = BufReader::new(raw.as_bytes());
+
+        assert!(matches!(Request::parse(&mut reader), Err(RequestError::UnknownMethod(_))));
+    }
+}
@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+/// An HTTP response a handler returns to the `Router`.
+pub struct Response {
+    pub status_code: u16,
+    pub reason: &'static str,
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+impl Response {
+    /// Constructs a response with the given status line and body.
+    pub fn new(status_code: u16, reason: &'static str, body: impl Into<String>) -> Self {
+        Self {
+            status_code,
+            reason,
+            headers: HashMap::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Shorthand for a `200 OK` response.
+    pub fn ok(body: impl Into<String>) -> Self {
+        Self::new(200, "OK", body)
+    }
+
+    /// Shorthand for a `404 Not Found` response.
+    pub fn not_found(body: impl Into<String>) -> Self {
+        Self::new(404, "Not Found", body)
+    }
+
+    /// Adds a header, replacing any previous value for `name`.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// Writes the status line, headers (plus `Content-Length`), and body to
+    /// `writer`.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write!(writer, "HTTP/1.1 {} {}\r\n", self.status_code, self.reason)?;
+        write!(writer, "Content-Length: {}\r\n", self.body.len())?;
+
+        for (name, value) in &self.headers {
+            write!(writer, "{name}: {value}\r\n")?;
+        }
+
+        write!(writer, "\r\n{}", self.body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_status_line_and_content_length() {
+        let response = Response::ok("hi");
+
+        let mut buf = Vec::new();
+        response.write_to(&mut buf).unwrap();
+        let rendered = String::from_utf8(buf).unwrap();
+
+        assert!(rendered.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(rendered.contains("Content-Length: 2\r\n"));
+        assert!(rendered.ends_with("\r\n\r\nhi"));
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use std::io::BufReader;
+use std::net::TcpStream;
+
+use crate::request::{Method, Request};
+use crate::response::Response;
+
+type Handler = Box<dyn Fn(&Request) -> Response + Send + Sync>;
+
+/// Dispatches incoming connections to handlers registered per `(Method, path)`.
+///
+/// Built with the `route`/`not_found` builder methods and then shared
+/// (typically behind an `Arc`) across the threads calling `dispatch`.
+pub struct Router {
+    routes: HashMap<(Method, String), Handler>,
+    not_found: Handler,
+}
+
+impl Router {
+    /// Creates a `Router` with no routes and a plain-text 404 fallback.
+    pub fn new() -> Self {
+        Self {
+            routes: HashMap::new(),
+            not_found: Box::new(|_req| Response::not_found("404 Not Found")),
+        }
+    }
+
+    /// Registers `handler` to serve `method` requests for `path`.
+    pub fn route<F>(mut self, method: Method, path: &str, handler: F) -> Self
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        self.routes.insert((method, path.to_string()), Box::new(handler));
+        self
+    }
+
+    /// Overrides the handler used when no route matches the request.
+    pub fn not_found<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        self.not_found = Box::new(handler);
+        self
+    }
+
+    /// Parses a request off `stream`, runs the matching handler (or the
+    /// not-found handler), and writes the response back.
+    pub fn dispatch(&self, mut stream: TcpStream) {
+        let mut reader = BufReader::new(&mut stream);
+
+        let request = match Request::parse(&mut reader) {
+            Ok(request) => request,
+            Err(_) => {
+                let _ = Response::new(400, "Bad Request", "400 Bad Request").write_to(&mut stream);
+                return;
+            }
+        };
+
+        let handler = self
+            .routes
+            .get(&(request.method, request.path.clone()))
+            .unwrap_or(&self.not_found);
+
+        let response = handler(&request);
+
+        let _ = response.write_to(&mut stream);
+    }
+}
+
+impl Default for Router {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn route_registers_handler_under_method_and_path() {
+        let router = Router::new().route(Method::Get, "/", |_req| Response::ok("home"));
+
+        assert!(router.routes.contains_key(&(Method::Get, "/".to_string())));
+        assert!(!router.routes.contains_key(&(Method::Get, "/missing".to_string())));
+    }
+
+    #[test]
+    fn dispatch_routes_matching_request_to_its_handler() {
+        let router = Router::new().route(Method::Get, "/hello", |_req| Response::ok("hello world"));
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let client = thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream.write_all(b"GET /hello HTTP/1.1\r\n\r\n").unwrap();
+            stream.shutdown(std::net::Shutdown::Write).unwrap();
+
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        });
+
+        let (stream, _) = listener.accept().unwrap();
+        router.dispatch(stream);
+
+        let response = client.join().unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("hello world"));
+    }
+
+    #[test]
+    fn dispatch_returns_bad_request_for_malformed_request_line() {
+        let router = Router::new();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let client = thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream.write_all(b"\r\n").unwrap();
+            stream.shutdown(std::net::Shutdown::Write).unwrap();
+
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        });
+
+        let (stream, _) = listener.accept().unwrap();
+        router.dispatch(stream);
+
+        let response = client.join().unwrap();
+        assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+    }
+}